@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+const DEFAULT_YABAI_PATH: &str = "/opt/homebrew/bin/yabai";
+const DEFAULT_GAP: u32 = 10;
+const DEFAULT_PADDING: u32 = 10;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    yabai_path: Option<PathBuf>,
+    icons: HashMap<String, String>,
+    tooltips: HashMap<String, String>,
+    gap: Option<u32>,
+    padding: Option<u32>,
+}
+
+impl Config {
+    pub fn load() -> Self {
+        let Some(path) = Self::config_path() else {
+            return Self::default();
+        };
+
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+
+        Self::parse(&contents, &path)
+    }
+
+    fn parse(contents: &str, path: &PathBuf) -> Self {
+        match toml::from_str(contents) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("failed to parse {}: {err}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    fn config_path() -> Option<PathBuf> {
+        Some(
+            dirs::home_dir()?
+                .join(".config")
+                .join("yabai-menu")
+                .join("config.toml"),
+        )
+    }
+
+    pub fn yabai_path(&self) -> PathBuf {
+        self.yabai_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_YABAI_PATH))
+    }
+
+    pub fn icon(&self, layout: &str) -> Option<&str> {
+        self.icons.get(layout).map(String::as_str)
+    }
+
+    pub fn tooltip(&self, layout: &str) -> Option<&str> {
+        self.tooltips.get(layout).map(String::as_str)
+    }
+
+    pub fn gap(&self) -> u32 {
+        self.gap.unwrap_or(DEFAULT_GAP)
+    }
+
+    pub fn padding(&self) -> u32 {
+        self.padding.unwrap_or(DEFAULT_PADDING)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_falls_back_to_defaults_on_invalid_toml() {
+        let config = Config::parse("not valid toml {{{", &PathBuf::from("config.toml"));
+        assert_eq!(config.gap(), DEFAULT_GAP);
+        assert_eq!(config.padding(), DEFAULT_PADDING);
+        assert_eq!(config.yabai_path(), PathBuf::from(DEFAULT_YABAI_PATH));
+    }
+
+    #[test]
+    fn parse_applies_overrides_from_valid_toml() {
+        let config = Config::parse("gap = 5\npadding = 20", &PathBuf::from("config.toml"));
+        assert_eq!(config.gap(), 5);
+        assert_eq!(config.padding(), 20);
+    }
+}
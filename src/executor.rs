@@ -0,0 +1,41 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use once_cell::sync::OnceCell;
+
+type Task = Box<dyn FnOnce() + Send>;
+
+struct Executor {
+    sender: Sender<Task>,
+}
+
+impl Executor {
+    fn new() -> Self {
+        let (sender, receiver) = mpsc::channel::<Task>();
+
+        thread::spawn(move || {
+            for task in receiver {
+                if let Err(err) = panic::catch_unwind(AssertUnwindSafe(task)) {
+                    eprintln!("background task panicked: {err:?}");
+                }
+            }
+        });
+
+        Self { sender }
+    }
+
+    fn spawn(&self, task: impl FnOnce() + Send + 'static) {
+        let _ = self.sender.send(Box::new(task));
+    }
+}
+
+pub fn spawn(task: impl FnOnce() + Send + 'static) {
+    background().spawn(task);
+}
+
+fn background() -> &'static Executor {
+    static INSTANCE: OnceCell<Executor> = OnceCell::new();
+
+    INSTANCE.get_or_init(Executor::new)
+}
@@ -2,8 +2,6 @@ use std::cell::RefCell;
 use std::path::PathBuf;
 use std::ptr::NonNull;
 use std::sync::{Arc, Condvar, Mutex};
-use std::thread;
-use std::time::Duration;
 
 use block2::{Block, ConcreteBlock, RcBlock};
 use cacao::appkit::{App, AppDelegate};
@@ -16,13 +14,26 @@ use icrate::objc2::{
     declare::IvarDrop, declare_class, msg_send, msg_send_id, mutability, sel, ClassType,
 };
 use icrate::AppKit::{
-    NSImage, NSMenuItem, NSStatusItem, NSWorkspace, NSWorkspaceActiveSpaceDidChangeNotification,
+    NSControlStateValueOff, NSControlStateValueOn, NSImage, NSMenu, NSMenuItem, NSStatusItem,
+    NSWorkspace, NSWorkspaceActiveSpaceDidChangeNotification,
 };
 use icrate::AppKit::{NSStatusBar, NSVariableStatusItemLength};
 use icrate::Foundation::{NSNotification, NSObject, NSString};
 use once_cell::sync::OnceCell;
 use serde::Deserialize;
 
+mod config;
+mod executor;
+mod signals;
+
+use config::Config;
+
+fn config() -> &'static Config {
+    static INSTANCE: OnceCell<Config> = OnceCell::new();
+
+    INSTANCE.get_or_init(Config::load)
+}
+
 #[derive(Debug, Deserialize)]
 #[allow(unused)]
 struct SpaceResponse {
@@ -32,6 +43,10 @@ struct SpaceResponse {
     r#type: YabaiSpaceLayout,
     label: String,
     display: u32,
+    #[serde(rename = "has-focus")]
+    has_focus: bool,
+    #[serde(rename = "is-visible")]
+    is_visible: bool,
 }
 
 struct Yabai {
@@ -39,27 +54,56 @@ struct Yabai {
 }
 
 impl Yabai {
-    fn get_layout_for_current_space(&self) -> SpaceResponse {
-        let output = cmd!(&self.yabai, "-m", "query", "--spaces", "--space")
-            .read()
-            .unwrap();
+    fn get_spaces(&self) -> std::io::Result<Vec<SpaceResponse>> {
+        let output = cmd!(&self.yabai, "-m", "query", "--spaces").read()?;
+
+        serde_json::from_str(&output)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+
+    fn change_space_layout(&self, space_index: u32, layout: &YabaiSpaceLayout) -> std::io::Result<()> {
+        cmd!(
+            &self.yabai,
+            "-m",
+            "space",
+            space_index.to_string(),
+            "--layout",
+            layout.to_string(),
+        )
+        .run()?;
+        Ok(())
+    }
+
+    fn add_signal(&self, event: &str, label: &str, action: &str) -> std::io::Result<()> {
+        cmd!(
+            &self.yabai,
+            "-m",
+            "signal",
+            "--add",
+            format!("event={event}"),
+            format!("label={label}"),
+            format!("action={action}"),
+        )
+        .run()?;
+        Ok(())
+    }
 
-        serde_json::from_str(&output).unwrap()
+    fn remove_signal(&self, label: &str) -> std::io::Result<()> {
+        cmd!(&self.yabai, "-m", "signal", "--remove", label).run()?;
+        Ok(())
     }
 
-    fn change_space_layout(&self, layout: &YabaiSpaceLayout) {
-        cmd!(&self.yabai, "-m", "space", "--layout", layout.to_string())
-            .run()
-            .unwrap();
+    fn set_config(&self, key: &str, value: impl ToString) -> std::io::Result<()> {
+        cmd!(&self.yabai, "-m", "config", key, value.to_string()).run()?;
+        Ok(())
     }
 }
 
 fn yabai() -> &'static Yabai {
     static INSTANCE: OnceCell<Yabai> = OnceCell::new();
 
-    INSTANCE.get_or_init(|| {
-        let yabai = PathBuf::from("/opt/homebrew/bin/yabai");
-        Yabai { yabai }
+    INSTANCE.get_or_init(|| Yabai {
+        yabai: config().yabai_path(),
     })
 }
 
@@ -69,6 +113,23 @@ enum YabaiSpaceLayout {
     #[default]
     Float,
     Bsp,
+    Stack,
+}
+
+impl YabaiSpaceLayout {
+    const ALL: [YabaiSpaceLayout; 3] = [
+        YabaiSpaceLayout::Float,
+        YabaiSpaceLayout::Bsp,
+        YabaiSpaceLayout::Stack,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            YabaiSpaceLayout::Float => "Float",
+            YabaiSpaceLayout::Bsp => "BSP",
+            YabaiSpaceLayout::Stack => "Stack",
+        }
+    }
 }
 
 impl ToString for YabaiSpaceLayout {
@@ -76,13 +137,41 @@ impl ToString for YabaiSpaceLayout {
         match self {
             YabaiSpaceLayout::Float => "float".to_string(),
             YabaiSpaceLayout::Bsp => "bsp".to_string(),
+            YabaiSpaceLayout::Stack => "stack".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct YabaiDisplaySpace {
+    display: u32,
+    space_index: u32,
+    label: String,
+    layout: YabaiSpaceLayout,
+    focused: bool,
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+enum YabaiStatus {
+    #[default]
+    Disconnected,
+    Connected(Vec<YabaiDisplaySpace>),
+}
+
+impl YabaiStatus {
+    fn focused_space(&self) -> Option<&YabaiDisplaySpace> {
+        match self {
+            YabaiStatus::Connected(spaces) => {
+                spaces.iter().find(|space| space.focused).or(spaces.first())
+            }
+            YabaiStatus::Disconnected => None,
         }
     }
 }
 
 #[derive(Default)]
 struct YabaiStateInner {
-    layout: YabaiSpaceLayout,
+    status: YabaiStatus,
 }
 
 #[derive(Clone)]
@@ -103,10 +192,14 @@ impl YabaiState {
         })
     }
 
-    fn set_layout(&self, new_layout: YabaiSpaceLayout) {
+    fn status(&self) -> YabaiStatus {
+        self.inner.0.lock().unwrap().status.clone()
+    }
+
+    fn set_status(&self, new_status: YabaiStatus) {
         let mut inner = self.inner.0.lock().unwrap();
-        if new_layout != inner.layout {
-            inner.layout = new_layout;
+        if new_status != inner.status {
+            inner.status = new_status;
             self.inner.1.notify_all();
 
             App::<YabaiMenu, Message>::dispatch_main(Message::UpdateIcon);
@@ -114,19 +207,41 @@ impl YabaiState {
     }
 
     fn update(&self) {
-        let new_layout = yabai().get_layout_for_current_space();
-        self.set_layout(new_layout.r#type);
+        let this = self.clone();
+        executor::spawn(move || {
+            let status = match yabai().get_spaces() {
+                Ok(spaces) => YabaiStatus::Connected(
+                    spaces
+                        .into_iter()
+                        .filter(|space| space.is_visible)
+                        .map(|space| YabaiDisplaySpace {
+                            display: space.display,
+                            space_index: space.index,
+                            label: space.label,
+                            layout: space.r#type,
+                            focused: space.has_focus,
+                        })
+                        .collect(),
+                ),
+                Err(_) => YabaiStatus::Disconnected,
+            };
+            this.set_status(status);
+        });
     }
 }
 
 struct YabaiMenuInner {
     status_item: RefCell<Option<Id<NSStatusItem>>>,
-    action: RefCell<Option<Id<RustAction>>>,
+    actions: RefCell<Vec<Id<RustAction>>>,
+    gaps_enabled: RefCell<bool>,
+    padding_enabled: RefCell<bool>,
 }
 
 #[derive(Debug)]
 enum Message {
     UpdateIcon,
+    GapsToggled(bool),
+    PaddingToggled(bool),
 }
 
 #[derive(Clone)]
@@ -139,55 +254,183 @@ impl YabaiMenu {
         Self {
             inner: Arc::new(YabaiMenuInner {
                 status_item: RefCell::new(None),
-                action: RefCell::new(None),
+                actions: RefCell::new(Vec::new()),
+                gaps_enabled: RefCell::new(false),
+                padding_enabled: RefCell::new(false),
             }),
         }
     }
 
-    fn get_icon(&self, layout: &YabaiSpaceLayout) -> &'static NSString {
-        match layout {
-            YabaiSpaceLayout::Float => ns_string!("macwindow.on.rectangle"),
-            YabaiSpaceLayout::Bsp => ns_string!("uiwindow.split.2x1"),
-        }
+    fn get_icon(&self, layout: &YabaiSpaceLayout) -> Id<NSString> {
+        let default = match layout {
+            YabaiSpaceLayout::Float => "macwindow.on.rectangle",
+            YabaiSpaceLayout::Bsp => "uiwindow.split.2x1",
+            YabaiSpaceLayout::Stack => "square.stack.3d.up",
+        };
+        NSString::from_str(config().icon(&layout.to_string()).unwrap_or(default))
     }
 
-    fn get_tooltip(&self, layout: &YabaiSpaceLayout) -> &'static NSString {
-        match layout {
-            YabaiSpaceLayout::Float => ns_string!("Float"),
-            YabaiSpaceLayout::Bsp => ns_string!("BSP"),
-        }
+    fn get_tooltip(&self, layout: &YabaiSpaceLayout) -> Id<NSString> {
+        let default = layout.label();
+        NSString::from_str(config().tooltip(&layout.to_string()).unwrap_or(default))
+    }
+
+    fn get_disconnected_icon(&self) -> Id<NSString> {
+        NSString::from_str(config().icon("disconnected").unwrap_or("bolt.slash"))
+    }
+
+    fn get_disconnected_tooltip(&self) -> Id<NSString> {
+        NSString::from_str(
+            config()
+                .tooltip("disconnected")
+                .unwrap_or("yabai not reachable"),
+        )
     }
 
     fn update_icon(&self) {
         unsafe {
-            let layout = { YabaiState::shared().inner.0.lock().unwrap().layout.clone() };
-            let icon_name = self.get_icon(&layout);
+            let status = YabaiState::shared().status();
+
+            let (icon_name, tooltip) = match status.focused_space() {
+                Some(space) => (self.get_icon(&space.layout), self.get_tooltip(&space.layout)),
+                None => (self.get_disconnected_icon(), self.get_disconnected_tooltip()),
+            };
+
             let item = self.inner.status_item.borrow();
             let item = item.as_ref().unwrap();
 
             if let Some(button) = item.as_ref().button() {
                 button.setImage(
                     NSImage::imageWithSystemSymbolName_accessibilityDescription(
-                        icon_name,
+                        &icon_name,
                         Some(ns_string!("1")),
                     )
                     .as_deref(),
                 );
-                button.setToolTip(Some(self.get_tooltip(&layout)));
+                button.setToolTip(Some(&tooltip));
             }
+
+            item.setMenu(Some(&self.build_menu(&status)));
         }
     }
 
-    fn toggle_layout(&self) {
-        let layout = {
-            let inner = YabaiState::shared().inner.0.lock().unwrap();
-            match inner.layout {
-                YabaiSpaceLayout::Float => YabaiSpaceLayout::Bsp,
-                YabaiSpaceLayout::Bsp => YabaiSpaceLayout::Float,
+    fn select_layout(&self, space_index: u32, layout: YabaiSpaceLayout) {
+        executor::spawn(move || match yabai().change_space_layout(space_index, &layout) {
+            Ok(()) => YabaiState::shared().update(),
+            Err(_) => YabaiState::shared().set_status(YabaiStatus::Disconnected),
+        });
+    }
+
+    fn toggle_gaps(&self) {
+        let attempted = !*self.inner.gaps_enabled.borrow();
+        executor::spawn(move || {
+            let value = if attempted { config().gap() } else { 0 };
+            match yabai().set_config("window_gap", value) {
+                Ok(()) => App::<YabaiMenu, Message>::dispatch_main(Message::GapsToggled(attempted)),
+                Err(_) => YabaiState::shared().set_status(YabaiStatus::Disconnected),
             }
-        };
-        yabai().change_space_layout(&layout);
-        YabaiState::shared().set_layout(layout);
+        });
+    }
+
+    fn toggle_padding(&self) {
+        let attempted = !*self.inner.padding_enabled.borrow();
+        executor::spawn(move || {
+            let value = if attempted { config().padding() } else { 0 };
+            let keys = ["top_padding", "bottom_padding", "left_padding", "right_padding"];
+            let ok = keys.iter().all(|key| yabai().set_config(key, value).is_ok());
+            if ok {
+                App::<YabaiMenu, Message>::dispatch_main(Message::PaddingToggled(attempted));
+            } else {
+                YabaiState::shared().set_status(YabaiStatus::Disconnected);
+            }
+        });
+    }
+
+    unsafe fn build_menu(&self, status: &YabaiStatus) -> Id<NSMenu> {
+        let menu = NSMenu::new();
+        self.inner.actions.borrow_mut().clear();
+
+        match status {
+            YabaiStatus::Connected(spaces) => {
+                for space in spaces {
+                    let title = NSString::from_str(&format!(
+                        "Display {}: space {} ({})",
+                        space.display, space.space_index, space.label
+                    ));
+                    let display_item = NSMenuItem::new();
+                    display_item.setTitle(&title);
+
+                    let submenu = NSMenu::new();
+                    for layout in YabaiSpaceLayout::ALL {
+                        let label = NSString::from_str(layout.label());
+                        let item = NSMenuItem::new();
+                        item.setTitle(&label);
+                        item.setState(if layout == space.layout {
+                            NSControlStateValueOn
+                        } else {
+                            NSControlStateValueOff
+                        });
+
+                        let this = self.clone();
+                        let space_index = space.space_index;
+                        let action_layout = layout.clone();
+                        let action = RustAction::new(move || {
+                            this.select_layout(space_index, action_layout.clone());
+                        });
+                        item.setTarget(Some(action.as_ref()));
+                        item.setAction(Some(sel!(call:)));
+
+                        submenu.addItem(&item);
+                        self.inner.actions.borrow_mut().push(action);
+                    }
+
+                    display_item.setSubmenu(Some(&submenu));
+                    menu.addItem(&display_item);
+                }
+            }
+            YabaiStatus::Disconnected => {
+                let item = NSMenuItem::new();
+                item.setTitle(ns_string!("yabai not reachable"));
+                item.setEnabled(false);
+                menu.addItem(&item);
+            }
+        }
+
+        menu.addItem(&NSMenuItem::separatorItem());
+
+        let gaps_item = NSMenuItem::new();
+        gaps_item.setTitle(ns_string!("Window Gaps"));
+        gaps_item.setState(if *self.inner.gaps_enabled.borrow() {
+            NSControlStateValueOn
+        } else {
+            NSControlStateValueOff
+        });
+        let this = self.clone();
+        let gaps_action = RustAction::new(move || {
+            this.toggle_gaps();
+        });
+        gaps_item.setTarget(Some(gaps_action.as_ref()));
+        gaps_item.setAction(Some(sel!(call:)));
+        menu.addItem(&gaps_item);
+        self.inner.actions.borrow_mut().push(gaps_action);
+
+        let padding_item = NSMenuItem::new();
+        padding_item.setTitle(ns_string!("Window Padding"));
+        padding_item.setState(if *self.inner.padding_enabled.borrow() {
+            NSControlStateValueOn
+        } else {
+            NSControlStateValueOff
+        });
+        let this = self.clone();
+        let padding_action = RustAction::new(move || {
+            this.toggle_padding();
+        });
+        padding_item.setTarget(Some(padding_action.as_ref()));
+        padding_item.setAction(Some(sel!(call:)));
+        menu.addItem(&padding_item);
+        self.inner.actions.borrow_mut().push(padding_action);
+
+        menu
     }
 }
 
@@ -197,24 +440,23 @@ impl Dispatcher for YabaiMenu {
     fn on_ui_message(&self, message: Self::Message) {
         match message {
             Message::UpdateIcon => self.update_icon(),
+            Message::GapsToggled(enabled) => {
+                *self.inner.gaps_enabled.borrow_mut() = enabled;
+                self.update_icon();
+            }
+            Message::PaddingToggled(enabled) => {
+                *self.inner.padding_enabled.borrow_mut() = enabled;
+                self.update_icon();
+            }
         }
     }
 }
 
 impl AppDelegate for YabaiMenu {
     fn did_finish_launching(&self) {
-        let this = self.clone();
         unsafe {
             let menubar = NSStatusBar::systemStatusBar();
             let item = menubar.statusItemWithLength(NSVariableStatusItemLength);
-            if let Some(button) = item.button() {
-                let action = RustAction::new(move || {
-                    this.toggle_layout();
-                });
-                button.setTarget(Some(action.as_ref()));
-                button.setAction(Some(sel!(call:)));
-                *self.inner.action.borrow_mut() = Some(action);
-            }
             *self.inner.status_item.borrow_mut() = Some(item);
             self.update_icon();
         }
@@ -302,14 +544,7 @@ fn main() {
 
     let delegate = YabaiMenu::new();
 
-    // state update loop
-    let _state = thread::spawn({
-        let state = state.clone();
-        move || loop {
-            thread::sleep(Duration::from_secs(1));
-            state.update();
-        }
-    });
+    signals::install(state.clone());
 
     let observer = WorkspaceObserver::new();
 
@@ -324,5 +559,50 @@ fn main() {
             );
     }
 
+    extern "C" fn cleanup() {
+        signals::cleanup();
+    }
+    unsafe {
+        libc::atexit(cleanup);
+    }
+
     App::new("fan.zeyi.yabai-menu", delegate.clone()).run();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn space(space_index: u32, focused: bool) -> YabaiDisplaySpace {
+        YabaiDisplaySpace {
+            display: 1,
+            space_index,
+            label: String::new(),
+            layout: YabaiSpaceLayout::Bsp,
+            focused,
+        }
+    }
+
+    #[test]
+    fn focused_space_returns_disconnected_as_none() {
+        assert_eq!(YabaiStatus::Disconnected.focused_space(), None);
+    }
+
+    #[test]
+    fn focused_space_returns_the_focused_entry() {
+        let status = YabaiStatus::Connected(vec![space(1, false), space(2, true)]);
+        assert_eq!(status.focused_space(), Some(&space(2, true)));
+    }
+
+    #[test]
+    fn focused_space_falls_back_to_first_when_none_focused() {
+        let status = YabaiStatus::Connected(vec![space(1, false), space(2, false)]);
+        assert_eq!(status.focused_space(), Some(&space(1, false)));
+    }
+
+    #[test]
+    fn focused_space_is_none_when_connected_with_no_spaces() {
+        let status = YabaiStatus::Connected(vec![]);
+        assert_eq!(status.focused_space(), None);
+    }
+}
@@ -0,0 +1,60 @@
+use std::io::Read;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::thread;
+
+use crate::{yabai, YabaiState};
+
+const SIGNAL_EVENTS: &[(&str, &str)] = &[
+    ("space_changed", "yabai-menu-space-changed"),
+    ("window_focused", "yabai-menu-window-focused"),
+    ("display_changed", "yabai-menu-display-changed"),
+];
+
+fn socket_path() -> PathBuf {
+    std::env::temp_dir().join("yabai-menu.sock")
+}
+
+pub fn install(state: YabaiState) {
+    let socket = socket_path();
+    let _ = std::fs::remove_file(&socket);
+
+    let listener = match UnixListener::bind(&socket) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("failed to bind {}: {err}", socket.display());
+            return;
+        }
+    };
+
+    for (event, label) in SIGNAL_EVENTS {
+        let action = format!("echo 1 | /usr/bin/nc -U {}", socket.display());
+        if let Err(err) = yabai().add_signal(event, label, &action) {
+            eprintln!("failed to register yabai signal {event}: {err}");
+        }
+    }
+
+    thread::spawn(move || {
+        for connection in listener.incoming() {
+            let Ok(mut connection) = connection else {
+                continue;
+            };
+
+            let mut buf = [0u8; 16];
+            let _ = connection.read(&mut buf);
+            drain(&connection);
+
+            state.update();
+        }
+    });
+}
+
+fn drain(_connection: &UnixStream) {}
+
+pub fn cleanup() {
+    for (_, label) in SIGNAL_EVENTS {
+        let _ = yabai().remove_signal(label);
+    }
+
+    let _ = std::fs::remove_file(socket_path());
+}